@@ -0,0 +1,190 @@
+// Throughput benchmark harness for the GMLAN algorithms. `calculate_key`,
+// `brute_force`, and the batched/SIMD path all have very different cost
+// profiles (one table walk vs. up to 256 of them, one seed vs. a whole
+// `SIMD_LANES`-wide batch), and the fastest path depends on the caller's
+// hardware and workload shape. `Workload::run` drives a configurable mix of
+// `calculate_key`/`brute_force` calls across multiple threads over a slice
+// of the seed space and reports latency percentiles and throughput so a
+// caller can compare paths empirically instead of guessing.
+
+use crate::gmlan;
+use std::time::{Duration, Instant};
+
+/// Relative weights between the two measured operations: a single
+/// [`gmlan::calculate_key`] call per op vs. a full [`gmlan::brute_force`]
+/// sweep per op. Weights don't need to sum to 1 — only their ratio matters.
+#[derive(Debug, Clone, Copy)]
+pub struct Mix {
+    pub calc: f64,
+    pub brute_force: f64,
+}
+
+impl Default for Mix {
+    /// All `calculate_key` calls, no `brute_force` sweeps.
+    fn default() -> Self {
+        Mix {
+            calc: 1.0,
+            brute_force: 0.0,
+        }
+    }
+}
+
+/// A configured benchmark run: how many threads to spread `ops` operations
+/// across, the calc/brute_force mix, and the slice of the 16-bit seed space
+/// to draw seeds from.
+#[derive(Debug, Clone)]
+pub struct Workload {
+    pub threads: usize,
+    pub ops: usize,
+    pub mix: Mix,
+    pub seed_range: std::ops::Range<u16>,
+}
+
+/// Latency percentiles and throughput for one operation kind, aggregated
+/// across every thread that ran it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpStats {
+    pub count: usize,
+    pub p50_us: f64,
+    pub p95_us: f64,
+    pub p99_us: f64,
+    pub throughput_per_sec: f64,
+}
+
+fn stats_from_samples(mut samples_ns: Vec<u64>, elapsed: Duration) -> OpStats {
+    if samples_ns.is_empty() {
+        return OpStats::default();
+    }
+    samples_ns.sort_unstable();
+
+    let percentile = |pct: f64| -> f64 {
+        let idx = (((samples_ns.len() - 1) as f64) * pct).round() as usize;
+        samples_ns[idx] as f64 / 1000.0 // ns -> us
+    };
+
+    OpStats {
+        count: samples_ns.len(),
+        p50_us: percentile(0.50),
+        p95_us: percentile(0.95),
+        p99_us: percentile(0.99),
+        throughput_per_sec: samples_ns.len() as f64 / elapsed.as_secs_f64(),
+    }
+}
+
+/// Operation count, elapsed time and throughput for one thread, so scaling
+/// across threads can be inspected rather than only the aggregate.
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadReport {
+    pub thread_index: usize,
+    pub ops: usize,
+    pub elapsed: Duration,
+    pub ops_per_sec: f64,
+}
+
+/// The result of a [`Workload::run`] call.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub threads: usize,
+    pub elapsed: Duration,
+    pub calculate_key: OpStats,
+    pub brute_force: OpStats,
+    pub per_thread: Vec<ThreadReport>,
+}
+
+struct ThreadSamples {
+    calc_ns: Vec<u64>,
+    brute_ns: Vec<u64>,
+}
+
+impl Workload {
+    /// Run this workload against `table`, partitioning `ops` operations
+    /// across `threads` threads (thread `t` strides through `seed_range` at
+    /// offsets `t`, `t + threads`, ..., wrapping at its end), and return
+    /// aggregate and per-thread throughput.
+    pub fn run(&self, table: &[u8]) -> Report {
+        let threads = self.threads.max(1);
+        let max_algo = ((table.len() / 13).max(1)) as u32;
+        let span = (self.seed_range.end as u32).saturating_sub(self.seed_range.start as u32).max(1);
+        let start = self.seed_range.start;
+
+        let calc_share = if self.mix.calc + self.mix.brute_force > 0.0 {
+            self.mix.calc / (self.mix.calc + self.mix.brute_force)
+        } else {
+            1.0
+        };
+
+        let started = Instant::now();
+
+        let per_thread_results: Vec<(ThreadSamples, Duration)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|thread_index| {
+                    scope.spawn(move || {
+                        let thread_ops = self.ops / threads + usize::from(thread_index < self.ops % threads);
+                        let mut samples = ThreadSamples {
+                            calc_ns: Vec::with_capacity(thread_ops),
+                            brute_ns: Vec::with_capacity(thread_ops),
+                        };
+
+                        // Bresenham-style interleaving of the two op kinds so
+                        // the mix is spread evenly across the run instead of
+                        // front-loaded (all calc ops, then all brute_force).
+                        let mut carried = 0.0;
+                        let thread_started = Instant::now();
+
+                        for i in 0..thread_ops {
+                            let seed_offset = (thread_index as u32 + (i as u32) * threads as u32) % span;
+                            let seed = start.wrapping_add(seed_offset as u16);
+                            let algo = (seed as u32 % max_algo) as u8;
+
+                            carried += calc_share;
+                            let run_calc = carried >= 1.0;
+                            if run_calc {
+                                carried -= 1.0;
+                            }
+
+                            if run_calc {
+                                let started = Instant::now();
+                                let _ = gmlan::calculate_key(seed, algo, table);
+                                samples.calc_ns.push(started.elapsed().as_nanos() as u64);
+                            } else {
+                                let started = Instant::now();
+                                let _ = gmlan::brute_force(seed, 0, table);
+                                samples.brute_ns.push(started.elapsed().as_nanos() as u64);
+                            }
+                        }
+
+                        (samples, thread_started.elapsed())
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let elapsed = started.elapsed();
+
+        let mut calc_ns = Vec::new();
+        let mut brute_ns = Vec::new();
+        let mut per_thread = Vec::with_capacity(threads);
+
+        for (thread_index, (samples, thread_elapsed)) in per_thread_results.into_iter().enumerate() {
+            let thread_ops = samples.calc_ns.len() + samples.brute_ns.len();
+            per_thread.push(ThreadReport {
+                thread_index,
+                ops: thread_ops,
+                elapsed: thread_elapsed,
+                ops_per_sec: thread_ops as f64 / thread_elapsed.as_secs_f64(),
+            });
+            calc_ns.extend(samples.calc_ns);
+            brute_ns.extend(samples.brute_ns);
+        }
+
+        Report {
+            threads,
+            elapsed,
+            calculate_key: stats_from_samples(calc_ns, elapsed),
+            brute_force: stats_from_samples(brute_ns, elapsed),
+            per_thread,
+        }
+    }
+}
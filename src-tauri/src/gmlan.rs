@@ -154,7 +154,7 @@ pub fn calculate_key(seed: u16, algo: u8, table: &[u8]) -> Result<u16, String> {
 pub fn brute_force(seed: u16, known_key: u16, table: &[u8]) -> Vec<u8> {
     let mut found_algos = Vec::new();
     let max_algo = (table.len() / 13) as u8;
-    
+
     for algo in 0..max_algo {
         if let Ok(calc) = calculate_key(seed, algo, table) {
             if calc == known_key {
@@ -164,3 +164,532 @@ pub fn brute_force(seed: u16, known_key: u16, table: &[u8]) -> Vec<u8> {
     }
     found_algos
 }
+
+// A single seed/key pair usually matches several algorithms in the table,
+// so `brute_force` alone leaves ambiguity. `brute_force_multi` and
+// `Narrower` intersect the candidate set across multiple observed pairs,
+// which converges to a single algorithm (or an empty set, if the pairs are
+// inconsistent) as more challenge/response rounds are captured.
+
+/// Run [`brute_force`] over every pair in `pairs` and intersect the results,
+/// returning only the algorithms consistent with all of them. Empty `pairs`
+/// yields an empty candidate set.
+pub fn brute_force_multi(pairs: &[(u16, u16)], table: &[u8]) -> Vec<u8> {
+    let mut pairs = pairs.iter();
+    let Some(&(seed, key)) = pairs.next() else {
+        return Vec::new();
+    };
+
+    let mut candidates = brute_force(seed, key, table);
+
+    for &(seed, key) in pairs {
+        if candidates.is_empty() {
+            break;
+        }
+        let matches = brute_force(seed, key, table);
+        candidates.retain(|algo| matches.contains(algo));
+    }
+
+    candidates
+}
+
+/// Incrementally narrows the candidate algorithm set as seed/key pairs are
+/// observed one at a time, for callers (e.g. a live capture UI) that want
+/// the candidate list to shrink as each new pair arrives rather than
+/// re-running [`brute_force_multi`] over the whole history every time.
+pub struct Narrower<'a> {
+    table: &'a [u8],
+    candidates: Option<Vec<u8>>,
+}
+
+impl<'a> Narrower<'a> {
+    /// Create a `Narrower` with no pairs observed yet.
+    pub fn new(table: &'a [u8]) -> Self {
+        Narrower {
+            table,
+            candidates: None,
+        }
+    }
+
+    /// Record an observed seed/key pair, narrowing the candidate set to the
+    /// algorithms consistent with it and every pair seen so far.
+    pub fn push(&mut self, seed: u16, key: u16) {
+        let matches = brute_force(seed, key, self.table);
+        self.candidates = Some(match self.candidates.take() {
+            None => matches,
+            Some(mut candidates) => {
+                candidates.retain(|algo| matches.contains(algo));
+                candidates
+            }
+        });
+    }
+
+    /// The current candidate algorithms, narrowed by every pair observed so
+    /// far. Empty before the first [`push`](Self::push) call.
+    pub fn candidates(&self) -> &[u8] {
+        self.candidates.as_deref().unwrap_or(&[])
+    }
+}
+
+// --- Precomputed reverse-lookup index ---
+//
+// `brute_force` re-runs `calculate_key` over every algorithm for each query,
+// which is wasteful when identifying algorithms across many captured
+// seed/key pairs. `build_index` computes every (seed, algorithm) pair once
+// over the *entire* 16-bit seed space (not a sample — `identify` does an
+// exact packed-key hash lookup, so any seed left unindexed would silently
+// never match a real capture) into a fixed-layout, open-addressed hash
+// table that `identify` can query in O(1) without touching `calculate_key`
+// again. The buffer is self-describing (magic/capacity/load-factor header)
+// so it can be written to disk and read back without any side information.
+
+const INDEX_MAGIC: [u8; 4] = *b"GMX1";
+const INDEX_HEADER_LEN: usize = 12;
+const INDEX_SLOT_LEN: usize = 9; // 4 byte packed key + 1 byte count + 4 algo bytes
+const INDEX_MAX_ALGOS_PER_SLOT: usize = 4;
+// Key bytes of an empty slot. u32::MAX is also the packed form of the real
+// pair (seed 0xFFFF, key 0xFFFF), so a slot is empty when its count byte is
+// 0, not when its key equals this value.
+const INDEX_EMPTY_KEY: u32 = u32::MAX;
+
+// A slot whose inline 4-algo capacity isn't enough (the same (seed, key)
+// pair is produced by more than `INDEX_MAX_ALGOS_PER_SLOT` algorithms)
+// stores this sentinel as its count byte. Its 4 algo bytes are then a u32
+// offset into the overflow pool appended after the slot table, where the
+// full algo list (u16 length + that many algo bytes) actually lives —
+// keeping every slot a fixed `INDEX_SLOT_LEN` bytes while still recording
+// every candidate algorithm instead of silently dropping any.
+const INDEX_OVERFLOW_SENTINEL: u8 = 0xFF;
+
+fn pack_seed_key(seed: u16, key: u16) -> u32 {
+    ((seed as u32) << 16) | key as u32
+}
+
+// Fibonacci (multiply-shift) hash of the packed seed/key: fast, and spreads
+// keys well across a power-of-two capacity without a division.
+fn index_hash(packed: u32, capacity: u32) -> usize {
+    const GOLDEN_RATIO: u32 = 0x9E37_79B1;
+    let shift = 32 - capacity.trailing_zeros();
+    (packed.wrapping_mul(GOLDEN_RATIO) >> shift) as usize
+}
+
+fn slot_offset(slot: usize) -> usize {
+    INDEX_HEADER_LEN + slot * INDEX_SLOT_LEN
+}
+
+/// Build a self-describing, on-disk reverse-lookup index over `table`: for
+/// every algorithm and *every* seed in the 16-bit seed space, records which
+/// algorithm(s) produce which (seed, key) pair, so [`identify`] can answer
+/// "which algorithm(s) could have produced this seed/key" in a single
+/// hashed lookup instead of `table.len() / 13` arithmetic passes. Every
+/// seed is indexed (not sampled), since a real capture's seed is
+/// effectively arbitrary and `identify` only matches exact packed keys. A
+/// (seed, key) pair produced by more than [`INDEX_MAX_ALGOS_PER_SLOT`]
+/// algorithms spills into an overflow pool appended after the slot table
+/// instead of being truncated, so `identify` always returns the complete
+/// candidate set that `brute_force` would.
+pub fn build_index(table: &[u8]) -> Vec<u8> {
+    let max_algo = (table.len() / 13) as u32;
+
+    let mut entries: Vec<(u32, u8)> = Vec::new();
+    for seed in 0..=u16::MAX {
+        for algo in 0..max_algo as u8 {
+            if let Ok(key) = calculate_key(seed, algo, table) {
+                entries.push((pack_seed_key(seed, key), algo));
+            }
+        }
+    }
+
+    // Keep the load factor under 50% so probe chains stay short.
+    let capacity = (entries.len().max(1) * 2).next_power_of_two() as u32;
+
+    let mut slot_keys = vec![INDEX_EMPTY_KEY; capacity as usize];
+    let mut slot_algos: Vec<Vec<u8>> = vec![Vec::new(); capacity as usize];
+
+    let mut occupied = 0u32;
+    for (packed, algo) in entries {
+        let mut slot = index_hash(packed, capacity);
+        loop {
+            if slot_algos[slot].is_empty() {
+                slot_keys[slot] = packed;
+                slot_algos[slot].push(algo);
+                occupied += 1;
+                break;
+            }
+            if slot_keys[slot] == packed {
+                if !slot_algos[slot].contains(&algo) {
+                    slot_algos[slot].push(algo);
+                }
+                break;
+            }
+            slot = (slot + 1) % capacity as usize;
+        }
+    }
+
+    let mut buf = vec![0u8; INDEX_HEADER_LEN + capacity as usize * INDEX_SLOT_LEN];
+    let mut overflow_pool: Vec<u8> = Vec::new();
+
+    for slot in 0..capacity as usize {
+        let off = slot_offset(slot);
+        buf[off..off + 4].copy_from_slice(&slot_keys[slot].to_le_bytes());
+
+        let algos = &slot_algos[slot];
+        if algos.is_empty() {
+            continue;
+        }
+
+        if algos.len() <= INDEX_MAX_ALGOS_PER_SLOT {
+            buf[off + 4] = algos.len() as u8;
+            buf[off + 5..off + 5 + algos.len()].copy_from_slice(algos);
+        } else {
+            let overflow_offset = overflow_pool.len() as u32;
+            overflow_pool.extend_from_slice(&(algos.len() as u16).to_le_bytes());
+            overflow_pool.extend_from_slice(algos);
+            buf[off + 4] = INDEX_OVERFLOW_SENTINEL;
+            buf[off + 5..off + 9].copy_from_slice(&overflow_offset.to_le_bytes());
+        }
+    }
+
+    buf.extend_from_slice(&overflow_pool);
+
+    let load_factor_percent = (occupied as u64 * 100 / capacity as u64) as u32;
+
+    let mut header = Vec::with_capacity(INDEX_HEADER_LEN);
+    header.extend_from_slice(&INDEX_MAGIC);
+    header.extend_from_slice(&capacity.to_le_bytes());
+    header.extend_from_slice(&load_factor_percent.to_le_bytes());
+    buf[..INDEX_HEADER_LEN].copy_from_slice(&header);
+
+    buf
+}
+
+/// Look up every algorithm consistent with `(seed, key)` in an index built
+/// by [`build_index`], via a single open-addressed hash probe. Returns the
+/// full candidate set even when it overflowed a slot's inline capacity.
+pub fn identify(index: &[u8], seed: u16, key: u16) -> Vec<u8> {
+    if index.len() < INDEX_HEADER_LEN || index[0..4] != INDEX_MAGIC[..] {
+        return Vec::new();
+    }
+    let capacity = u32::from_le_bytes(index[4..8].try_into().unwrap());
+    if capacity == 0 || !capacity.is_power_of_two() {
+        return Vec::new();
+    }
+
+    let packed = pack_seed_key(seed, key);
+    let mut slot = index_hash(packed, capacity);
+
+    for _ in 0..capacity {
+        let off = slot_offset(slot);
+        if off + INDEX_SLOT_LEN > index.len() {
+            break;
+        }
+        let slot_key = u32::from_le_bytes(index[off..off + 4].try_into().unwrap());
+        let count = index[off + 4];
+        if count == 0 {
+            break;
+        }
+        if slot_key == packed {
+            if count == INDEX_OVERFLOW_SENTINEL {
+                let overflow_offset = u32::from_le_bytes(index[off + 5..off + 9].try_into().unwrap()) as usize;
+                let pool_start = INDEX_HEADER_LEN + capacity as usize * INDEX_SLOT_LEN;
+                let len_start = pool_start + overflow_offset;
+                if len_start + 2 > index.len() {
+                    return Vec::new();
+                }
+                let len = u16::from_le_bytes(index[len_start..len_start + 2].try_into().unwrap()) as usize;
+                let algos_start = len_start + 2;
+                if algos_start + len > index.len() {
+                    return Vec::new();
+                }
+                return index[algos_start..algos_start + len].to_vec();
+            }
+            let count = (count as usize).min(INDEX_MAX_ALGOS_PER_SLOT);
+            return index[off + 5..off + 5 + count].to_vec();
+        }
+        slot = (slot + 1) % capacity as usize;
+    }
+
+    Vec::new()
+}
+
+// --- SIMD batch computation ---
+//
+// Every opcode is a plain 16-bit ALU operation with a fixed, per-algorithm
+// immediate, so sweeping seeds is embarrassingly parallel across lanes.
+// `calculate_key_batch` processes `SIMD_LANES` seeds per iteration with
+// `std::simd` when built with the (nightly-only) "simd" Cargo feature, and
+// falls back to a portable scalar path otherwise. This is a compile-time
+// choice, not runtime CPU-feature detection or lane-width selection — there
+// is exactly one SIMD width (`u16x16`), picked for the feature build.
+const SIMD_LANES: usize = 16;
+
+/// Compute `calculate_key` for every seed in `seeds` against the same
+/// `algo`. Functionally equivalent to mapping `calculate_key` over `seeds`,
+/// just processed `SIMD_LANES` at a time when the `simd` feature is on.
+pub fn calculate_key_batch(seeds: &[u16], algo: u8, table: &[u8]) -> Result<Vec<u16>, String> {
+    #[cfg(feature = "simd")]
+    {
+        calculate_key_batch_simd(seeds, algo, table)
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        calculate_key_batch_scalar(seeds, algo, table)
+    }
+}
+
+fn calculate_key_batch_scalar(seeds: &[u16], algo: u8, table: &[u8]) -> Result<Vec<u16>, String> {
+    seeds.iter().map(|&seed| calculate_key(seed, algo, table)).collect()
+}
+
+#[cfg(feature = "simd")]
+fn calculate_key_batch_simd(seeds: &[u16], algo: u8, table: &[u8]) -> Result<Vec<u16>, String> {
+    use std::simd::u16x16;
+
+    if seeds.is_empty() {
+        return Ok(Vec::new());
+    }
+    if algo == 0 {
+        return Ok(seeds.iter().map(|&seed| !seed).collect());
+    }
+
+    let idx = (algo as usize) * 13;
+    if idx + 12 >= table.len() {
+        return Err("Algorithm definition out of bounds".to_string());
+    }
+
+    let mut out = vec![0u16; seeds.len()];
+    let mut offset = 0;
+    for chunk in seeds.chunks(SIMD_LANES) {
+        let mut lanes = [0u16; SIMD_LANES];
+        lanes[..chunk.len()].copy_from_slice(chunk);
+        let mut v = u16x16::from_array(lanes);
+
+        let mut cursor = idx;
+        for _ in 0..4 {
+            let opcode = table[cursor];
+            let hh = table[cursor + 1];
+            let ll = table[cursor + 2];
+            v = apply_op_simd(v, opcode, hh, ll)?;
+            cursor += 3;
+        }
+
+        let result = v.to_array();
+        out[offset..offset + chunk.len()].copy_from_slice(&result[..chunk.len()]);
+        offset += chunk.len();
+    }
+
+    Ok(out)
+}
+
+#[cfg(feature = "simd")]
+fn apply_op_simd(v: std::simd::u16x16, opcode: u8, hh: u8, ll: u8) -> Result<std::simd::u16x16, String> {
+    use std::simd::u16x16;
+
+    let splat = u16x16::splat;
+
+    let result = match opcode {
+        OP_BYTE_SWAP => (v << splat(8)) | (v >> splat(8)),
+        OP_ADD_HL => {
+            let add_val = ((hh as u16) << 8) | ll as u16;
+            v + splat(add_val)
+        }
+        OP_COMPLEMENT => {
+            let negated = !v;
+            if hh < ll {
+                negated + splat(1)
+            } else {
+                negated
+            }
+        }
+        OP_AND_LH => {
+            let and_val = ((ll as u16) << 8) | hh as u16;
+            v & splat(and_val)
+        }
+        OP_ROL => {
+            let shift = (hh & 0x0F) as u16;
+            if shift == 0 {
+                v
+            } else {
+                (v << splat(shift)) | (v >> splat(16 - shift))
+            }
+        }
+        OP_OR_HL => {
+            let or_val = ((ll as u16) << 8) | hh as u16;
+            v | splat(or_val)
+        }
+        OP_ROR => {
+            let shift = (ll & 0x0F) as u16;
+            if shift == 0 {
+                v
+            } else {
+                (v >> splat(shift)) | (v << splat(16 - shift))
+            }
+        }
+        OP_ADD_LH => {
+            let add_val = ((ll as u16) << 8) | hh as u16;
+            v + splat(add_val)
+        }
+        OP_SWAP_ADD => {
+            let swapped = (v << splat(8)) | (v >> splat(8));
+            if hh >= ll {
+                let add_val = ((hh as u16) << 8) | ll as u16;
+                swapped + splat(add_val)
+            } else {
+                let add_val = ((ll as u16) << 8) | hh as u16;
+                swapped + splat(add_val)
+            }
+        }
+        OP_SUB_HL => {
+            let sub_val = ((hh as u16) << 8) | ll as u16;
+            v - splat(sub_val)
+        }
+        OP_SUB_LH => {
+            let sub_val = ((ll as u16) << 8) | hh as u16;
+            v - splat(sub_val)
+        }
+        _ => return Err(format!("Unknown opcode: {:02X}", opcode)),
+    };
+
+    Ok(result)
+}
+
+// --- Precompiled variable-length instruction program ---
+//
+// `calculate_key` hardwires exactly four operations and re-parses the raw
+// table bytes on every call. `compile_algo` decodes an algorithm's bytes
+// once into a `Program` of `Op`s, each carrying its precomputed immediate
+// (the packed add/and/or constant or rotate amount), so `run` can execute
+// it with no table indexing or opcode matching in the hot loop. Reuse the
+// compiled `Program` across an entire seed sweep instead of recompiling it
+// per seed.
+
+/// Terminates an algorithm's operation list early, for tables that use
+/// fewer than [`ALGO_MAX_OPS`] operations.
+pub const OP_TERMINATOR: u8 = 0x00;
+
+/// The operation count `calculate_key` hardcodes; `compile_algo` stops
+/// decoding at this many ops even without hitting a terminator.
+pub const ALGO_MAX_OPS: usize = 4;
+
+/// A single decoded GMLAN algorithm operation with its immediate(s)
+/// precomputed from the table's `hh`/`ll` bytes, so `run` never has to
+/// re-derive them.
+#[derive(Debug, Clone, Copy)]
+pub enum Op {
+    InvertSeed,
+    ByteSwap,
+    AddHl(u16),
+    Complement(bool),
+    AndLh(u16),
+    Rol(u32),
+    OrHl(u16),
+    Ror(u32),
+    AddLh(u16),
+    SwapAdd(u16),
+    SubHl(u16),
+    SubLh(u16),
+}
+
+/// A decoded, ready-to-run algorithm, produced once by [`compile_algo`] and
+/// reusable across an entire seed space.
+#[derive(Debug, Clone)]
+pub struct Program {
+    ops: Vec<Op>,
+}
+
+/// Decode algorithm `algo`'s bytes from `table` into a [`Program`], once.
+pub fn compile_algo(table: &[u8], algo: u8) -> Result<Program, String> {
+    if algo == 0 {
+        return Ok(Program { ops: vec![Op::InvertSeed] });
+    }
+
+    let idx = (algo as usize) * 13;
+    if idx + 12 >= table.len() {
+        return Err("Algorithm definition out of bounds".to_string());
+    }
+
+    let mut ops = Vec::with_capacity(ALGO_MAX_OPS);
+    let mut cursor = idx;
+
+    for _ in 0..ALGO_MAX_OPS {
+        let opcode = table[cursor];
+        if opcode == OP_TERMINATOR {
+            break;
+        }
+        let hh = table[cursor + 1];
+        let ll = table[cursor + 2];
+
+        let op = match opcode {
+            OP_BYTE_SWAP => Op::ByteSwap,
+            OP_ADD_HL => Op::AddHl(((hh as u16) << 8) | ll as u16),
+            OP_COMPLEMENT => Op::Complement(hh < ll),
+            OP_AND_LH => Op::AndLh(((ll as u16) << 8) | hh as u16),
+            OP_ROL => Op::Rol((hh & 0x0F) as u32),
+            OP_OR_HL => Op::OrHl(((ll as u16) << 8) | hh as u16),
+            OP_ROR => Op::Ror((ll & 0x0F) as u32),
+            OP_ADD_LH => Op::AddLh(((ll as u16) << 8) | hh as u16),
+            OP_SWAP_ADD => {
+                let add_val = if hh >= ll {
+                    ((hh as u16) << 8) | ll as u16
+                } else {
+                    ((ll as u16) << 8) | hh as u16
+                };
+                Op::SwapAdd(add_val)
+            }
+            OP_SUB_HL => Op::SubHl(((hh as u16) << 8) | ll as u16),
+            OP_SUB_LH => Op::SubLh(((ll as u16) << 8) | hh as u16),
+            _ => return Err(format!("Unknown opcode: {:02X}", opcode)),
+        };
+
+        ops.push(op);
+        cursor += 3;
+    }
+
+    Ok(Program { ops })
+}
+
+/// Execute a compiled [`Program`] against `seed`. No table indexing or
+/// opcode matching happens here — everything was resolved by `compile_algo`.
+pub fn run(program: &Program, seed: u16) -> u16 {
+    let mut val = seed;
+
+    for op in &program.ops {
+        val = match *op {
+            Op::InvertSeed => !val,
+            Op::ByteSwap => op_05(val),
+            Op::AddHl(add_val) => w(val as u32 + add_val as u32),
+            Op::Complement(plus_one) => {
+                let negated = w(!(val as u32));
+                if plus_one {
+                    w(negated as u32 + 1)
+                } else {
+                    negated
+                }
+            }
+            Op::AndLh(and_val) => w(val as u32 & and_val as u32),
+            Op::Rol(shift) => {
+                if shift == 0 {
+                    val
+                } else {
+                    let v = val as u32;
+                    w((v << shift) | (v >> (16 - shift)))
+                }
+            }
+            Op::OrHl(or_val) => w(val as u32 | or_val as u32),
+            Op::Ror(shift) => {
+                if shift == 0 {
+                    val
+                } else {
+                    let v = val as u32;
+                    w((v >> shift) | (v << (16 - shift)))
+                }
+            }
+            Op::AddLh(add_val) => w(val as u32 + add_val as u32),
+            Op::SwapAdd(add_val) => w(op_05(val) as u32 + add_val as u32),
+            Op::SubHl(sub_val) => w((Wrapping(val as u32) - Wrapping(sub_val as u32)).0),
+            Op::SubLh(sub_val) => w((Wrapping(val as u32) - Wrapping(sub_val as u32)).0),
+        };
+    }
+
+    val
+}
@@ -0,0 +1,235 @@
+// Software ISO-TP (ISO 15765-2) segmentation layer over a raw CAN channel.
+//
+// Not every J2534 DLL implements the ISO15765 protocol in firmware, and even
+// when it does, callers sometimes want full control of multi-frame
+// sequencing for GM's longer SecurityAccess/routine payloads. This module
+// drives the raw CAN channel directly and does the Single/First/Consecutive
+// Frame bookkeeping itself.
+
+use crate::j2534::{J2534Driver, J2534Error, PassThruMsg, PassThruStatus, ProtocolKind};
+use std::time::{Duration, Instant};
+
+const PCI_SINGLE_FRAME: u8 = 0x00;
+const PCI_FIRST_FRAME: u8 = 0x10;
+const PCI_CONSECUTIVE_FRAME: u8 = 0x20;
+const PCI_FLOW_CONTROL: u8 = 0x30;
+
+const FC_CONTINUE_TO_SEND: u8 = 0x00;
+const FC_WAIT: u8 = 0x01;
+const FC_OVERFLOW: u8 = 0x02;
+
+/// Tuning knobs for the software ISO-TP transport. `block_size`/`st_min` are
+/// the Flow Control values *we* advertise to the sender when receiving;
+/// they follow the same encoding as the ISO15765_BS/ISO15765_STMIN IOCTL
+/// params used by the hardware transport.
+#[derive(Debug, Clone, Copy)]
+pub struct IsoTpOptions {
+    pub pad_byte: u8,
+    pub block_size: u8,
+    pub st_min: u8,
+}
+
+impl Default for IsoTpOptions {
+    fn default() -> Self {
+        IsoTpOptions {
+            pad_byte: 0x00,
+            block_size: 0,
+            st_min: 0,
+        }
+    }
+}
+
+fn pad_frame(frame: &mut Vec<u8>, pad_byte: u8) {
+    while frame.len() < 8 {
+        frame.push(pad_byte);
+    }
+}
+
+/// Decode an ISO 15765-2 STmin byte into the delay to wait between
+/// Consecutive Frames: 0x00-0x7F are milliseconds, 0xF1-0xF9 are
+/// 100-900 microsecond steps. Anything else is treated as no delay.
+fn st_min_duration(st_min: u8) -> Duration {
+    match st_min {
+        0x00..=0x7F => Duration::from_millis(st_min as u64),
+        0xF1..=0xF9 => Duration::from_micros((st_min as u64 - 0xF0) * 100),
+        _ => Duration::from_millis(0),
+    }
+}
+
+impl J2534Driver {
+    /// Send a payload over software ISO-TP on the raw CAN channel, segmenting
+    /// it into a Single Frame or a First Frame + Consecutive Frames as needed.
+    pub fn send_isotp(
+        &self,
+        tx_id: u32,
+        rx_id: u32,
+        data: &[u8],
+        opts: IsoTpOptions,
+        timeout_ms: u32,
+    ) -> Result<(), J2534Error> {
+        if data.len() > 0xFFF {
+            return Err(J2534Error::InvalidConfig(format!(
+                "ISO-TP payload of {} bytes exceeds the 12-bit First Frame length field (max 4095)",
+                data.len()
+            )));
+        }
+
+        let channel_id = self.channel_id(ProtocolKind::Can)?;
+
+        if data.len() <= 7 {
+            let mut frame = vec![PCI_SINGLE_FRAME | data.len() as u8];
+            frame.extend_from_slice(data);
+            pad_frame(&mut frame, opts.pad_byte);
+            self.write_msgs(channel_id, vec![PassThruMsg::new_can(tx_id, &frame)], timeout_ms)?;
+            return Ok(());
+        }
+
+        let len = data.len() as u16;
+        let mut first_frame = vec![
+            PCI_FIRST_FRAME | ((len >> 8) as u8 & 0x0F),
+            (len & 0xFF) as u8,
+        ];
+        first_frame.extend_from_slice(&data[0..6]);
+        pad_frame(&mut first_frame, opts.pad_byte);
+        self.write_msgs(channel_id, vec![PassThruMsg::new_can(tx_id, &first_frame)], timeout_ms)?;
+
+        let mut remaining = &data[6..];
+        let mut seq = 1u8;
+        let (mut block_size, mut st_min) = self.await_flow_control(channel_id, rx_id, timeout_ms)?;
+        let mut sent_in_block = 0u32;
+
+        while !remaining.is_empty() {
+            let take = remaining.len().min(7);
+            let mut frame = vec![PCI_CONSECUTIVE_FRAME | seq];
+            frame.extend_from_slice(&remaining[..take]);
+            pad_frame(&mut frame, opts.pad_byte);
+            self.write_msgs(channel_id, vec![PassThruMsg::new_can(tx_id, &frame)], timeout_ms)?;
+
+            remaining = &remaining[take..];
+            seq = (seq + 1) & 0x0F;
+            sent_in_block += 1;
+
+            if remaining.is_empty() {
+                break;
+            }
+
+            if block_size != 0 && sent_in_block >= block_size as u32 {
+                let fc = self.await_flow_control(channel_id, rx_id, timeout_ms)?;
+                block_size = fc.0;
+                st_min = fc.1;
+                sent_in_block = 0;
+            } else {
+                std::thread::sleep(st_min_duration(st_min));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Receive a payload over software ISO-TP, sending our own Flow Control
+    /// frame and reassembling Consecutive Frames until the declared length
+    /// is reached.
+    pub fn recv_isotp(&self, rx_id: u32, tx_id: u32, opts: IsoTpOptions, timeout_ms: u32) -> Result<Vec<u8>, J2534Error> {
+        let channel_id = self.channel_id(ProtocolKind::Can)?;
+        let first = self.wait_for_frame(channel_id, rx_id, timeout_ms)?;
+        if first.is_empty() {
+            return Err(J2534Error::InvalidConfig("Empty ISO-TP frame".to_string()));
+        }
+
+        match first[0] & 0xF0 {
+            PCI_SINGLE_FRAME => {
+                let len = (first[0] & 0x0F) as usize;
+                if first.len() < 1 + len {
+                    return Err(J2534Error::InvalidConfig("ISO-TP single frame shorter than declared length".to_string()));
+                }
+                Ok(first[1..1 + len].to_vec())
+            }
+            PCI_FIRST_FRAME => {
+                if first.len() < 2 {
+                    return Err(J2534Error::InvalidConfig("ISO-TP first frame shorter than its PCI".to_string()));
+                }
+                let len = (((first[0] & 0x0F) as usize) << 8) | first[1] as usize;
+                let mut data = Vec::with_capacity(len);
+                data.extend_from_slice(&first[2..]);
+
+                let mut fc = vec![PCI_FLOW_CONTROL | FC_CONTINUE_TO_SEND, opts.block_size, opts.st_min];
+                pad_frame(&mut fc, opts.pad_byte);
+                self.write_msgs(channel_id, vec![PassThruMsg::new_can(tx_id, &fc)], timeout_ms)?;
+
+                let mut expected_seq = 1u8;
+                let mut received_in_block = 0u32;
+                while data.len() < len {
+                    let cf = self.wait_for_frame(channel_id, rx_id, timeout_ms)?;
+                    if cf.is_empty() || cf[0] & 0xF0 != PCI_CONSECUTIVE_FRAME {
+                        return Err(J2534Error::InvalidConfig("Expected ISO-TP consecutive frame".to_string()));
+                    }
+                    let seq = cf[0] & 0x0F;
+                    if seq != expected_seq {
+                        return Err(J2534Error::InvalidConfig(format!(
+                            "ISO-TP sequence mismatch: expected {expected_seq}, got {seq}"
+                        )));
+                    }
+                    let take = (len - data.len()).min(cf.len() - 1);
+                    data.extend_from_slice(&cf[1..1 + take]);
+                    expected_seq = (expected_seq + 1) & 0x0F;
+
+                    // The sender pauses after each block of `block_size` frames
+                    // until it gets another Flow Control frame.
+                    received_in_block += 1;
+                    if opts.block_size != 0 && received_in_block >= opts.block_size as u32 && data.len() < len {
+                        self.write_msgs(channel_id, vec![PassThruMsg::new_can(tx_id, &fc)], timeout_ms)?;
+                        received_in_block = 0;
+                    }
+                }
+
+                Ok(data)
+            }
+            _ => Err(J2534Error::InvalidConfig("Expected ISO-TP single or first frame".to_string())),
+        }
+    }
+
+    /// Wait for a Flow Control frame from `rx_id`, transparently looping
+    /// past WAIT frames (which reset the response timer) and failing on
+    /// Overflow/abort.
+    fn await_flow_control(&self, channel_id: u32, rx_id: u32, timeout_ms: u32) -> Result<(u8, u8), J2534Error> {
+        loop {
+            let fc = self.wait_for_frame(channel_id, rx_id, timeout_ms)?;
+            if fc.len() < 3 || fc[0] & 0xF0 != PCI_FLOW_CONTROL {
+                return Err(J2534Error::InvalidConfig("Expected ISO-TP flow control frame".to_string()));
+            }
+            match fc[0] & 0x0F {
+                FC_CONTINUE_TO_SEND => return Ok((fc[1], fc[2])),
+                FC_WAIT => continue,
+                FC_OVERFLOW => return Err(J2534Error::InvalidConfig("ISO-TP flow control overflow/abort".to_string())),
+                other => return Err(J2534Error::InvalidConfig(format!("Unknown ISO-TP flow status: {other:#X}"))),
+            }
+        }
+    }
+
+    /// Poll `read_msgs` until a CAN frame with the expected arbitration ID
+    /// arrives or `timeout_ms` elapses, returning the frame's payload
+    /// (stripped of the 4-byte ID prefix `PassThruMsg` prepends).
+    fn wait_for_frame(&self, channel_id: u32, expected_id: u32, timeout_ms: u32) -> Result<Vec<u8>, J2534Error> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+
+        loop {
+            let remaining_ms = deadline.saturating_duration_since(Instant::now()).as_millis() as u32;
+            if remaining_ms == 0 {
+                return Err(J2534Error::PassThruError {
+                    status: PassThruStatus::Timeout,
+                    detail: None,
+                });
+            }
+
+            for msg in self.read_msgs(channel_id, 1, remaining_ms)? {
+                if msg.data.len() < 4 {
+                    continue;
+                }
+                let id = u32::from_be_bytes([msg.data[0], msg.data[1], msg.data[2], msg.data[3]]);
+                if id == expected_id {
+                    return Ok(msg.data[4..].to_vec());
+                }
+            }
+        }
+    }
+}
@@ -6,8 +6,26 @@ use thiserror::Error;
 
 // J2534 Constants
 pub const PASSTHRU_ERR_SUCCESS: i32 = 0;
+pub const ERR_NOT_SUPPORTED: i32 = 0x00000001;
+pub const ERR_INVALID_CHANNEL_ID: i32 = 0x00000002;
+pub const ERR_INVALID_PROTOCOL_ID: i32 = 0x00000003;
+pub const ERR_NULL_PARAMETER: i32 = 0x00000004;
+pub const ERR_INVALID_IOCTL_VALUE: i32 = 0x00000005;
+pub const ERR_INVALID_FLAGS: i32 = 0x00000006;
+pub const ERR_FAILED: i32 = 0x00000007;
+pub const ERR_DEVICE_NOT_CONNECTED: i32 = 0x00000008;
 pub const ERR_TIMEOUT: i32 = 0x00000009;
-pub const ERR_BUFFER_EMPTY: i32 = 0x00000042;
+pub const ERR_BUFFER_EMPTY: i32 = 0x00000010;
+pub const ERR_BUFFER_FULL: i32 = 0x00000011;
+pub const ERR_BUFFER_OVERFLOW: i32 = 0x00000012;
+pub const ERR_PIN_INVALID: i32 = 0x00000013;
+pub const ERR_CHANNEL_IN_USE: i32 = 0x00000014;
+pub const ERR_MSG_PROTOCOL_ID: i32 = 0x00000015;
+pub const ERR_INVALID_FILTER_ID: i32 = 0x00000016;
+pub const ERR_NO_FLOW_CONTROL: i32 = 0x00000017;
+pub const ERR_NOT_UNIQUE: i32 = 0x00000018;
+pub const ERR_INVALID_BAUDRATE: i32 = 0x00000019;
+pub const ERR_INVALID_DEVICE_ID: i32 = 0x0000001A;
 pub const CAN: u32 = 0x00000006;
 pub const CAN_29BIT_ID: u32 = 0x00000100;
 pub const ISO15765: u32 = 0x00000007;
@@ -44,14 +62,94 @@ impl ProtocolKind {
     }
 }
 
+/// A decoded PassThru status code, covering the codes defined by the J2534
+/// spec. `Unknown` preserves the raw value for anything a given vendor DLL
+/// returns outside the standard range.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PassThruStatus {
+    #[error("Function not supported by this device/DLL")]
+    NotSupported,
+    #[error("Invalid channel ID")]
+    InvalidChannelId,
+    #[error("Invalid protocol ID")]
+    InvalidProtocolId,
+    #[error("Null parameter passed to function")]
+    NullParameter,
+    #[error("Invalid IOCTL value")]
+    InvalidIoctlValue,
+    #[error("Invalid flags")]
+    InvalidFlags,
+    #[error("Function call failed")]
+    Failed,
+    #[error("Device is not connected")]
+    DeviceNotConnected,
+    #[error("Function call timed out")]
+    Timeout,
+    #[error("Receive buffer is empty")]
+    BufferEmpty,
+    #[error("Transmit buffer is full")]
+    BufferFull,
+    #[error("Transmit buffer overflowed, data lost")]
+    BufferOverflow,
+    #[error("Invalid pin number")]
+    PinInvalid,
+    #[error("Channel is already in use")]
+    ChannelInUse,
+    #[error("Protocol ID in message does not match channel")]
+    MsgProtocolId,
+    #[error("Invalid filter ID")]
+    InvalidFilterId,
+    #[error("No flow control filter set for this channel")]
+    NoFlowControl,
+    #[error("IOCTL parameter/value pair is not unique")]
+    NotUnique,
+    #[error("Invalid baud rate for this protocol")]
+    InvalidBaudrate,
+    #[error("Invalid device ID")]
+    InvalidDeviceId,
+    #[error("Unknown PassThru status code: {0:#06X}")]
+    Unknown(i32),
+}
+
+impl PassThruStatus {
+    fn from_code(code: i32) -> Self {
+        match code {
+            ERR_NOT_SUPPORTED => Self::NotSupported,
+            ERR_INVALID_CHANNEL_ID => Self::InvalidChannelId,
+            ERR_INVALID_PROTOCOL_ID => Self::InvalidProtocolId,
+            ERR_NULL_PARAMETER => Self::NullParameter,
+            ERR_INVALID_IOCTL_VALUE => Self::InvalidIoctlValue,
+            ERR_INVALID_FLAGS => Self::InvalidFlags,
+            ERR_FAILED => Self::Failed,
+            ERR_DEVICE_NOT_CONNECTED => Self::DeviceNotConnected,
+            ERR_TIMEOUT => Self::Timeout,
+            ERR_BUFFER_EMPTY => Self::BufferEmpty,
+            ERR_BUFFER_FULL => Self::BufferFull,
+            ERR_BUFFER_OVERFLOW => Self::BufferOverflow,
+            ERR_PIN_INVALID => Self::PinInvalid,
+            ERR_CHANNEL_IN_USE => Self::ChannelInUse,
+            ERR_MSG_PROTOCOL_ID => Self::MsgProtocolId,
+            ERR_INVALID_FILTER_ID => Self::InvalidFilterId,
+            ERR_NO_FLOW_CONTROL => Self::NoFlowControl,
+            ERR_NOT_UNIQUE => Self::NotUnique,
+            ERR_INVALID_BAUDRATE => Self::InvalidBaudrate,
+            ERR_INVALID_DEVICE_ID => Self::InvalidDeviceId,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
 #[derive(Error, Debug, Serialize)]
 pub enum J2534Error {
     #[error("DLL load failed: {0}")]
     LoadError(String),
     #[error("Function lookup failed: {0}")]
     SymbolError(String),
-    #[error("J2534 Error Code: {0}")]
-    PassThruError(i32),
+    #[error("{status}{}", detail.as_deref().map(|d| format!(" — device says: {d}")).unwrap_or_default())]
+    PassThruError {
+        status: PassThruStatus,
+        detail: Option<String>,
+    },
     #[error("Device not open")]
     NotConnected,
     #[error("Channel not open: {0}")]
@@ -60,6 +158,8 @@ pub enum J2534Error {
     InvalidConfig(String),
     #[error("Version read failed: {0}")]
     VersionError(String),
+    #[error("UDS negative response for service {sid:#04X}: NRC {nrc:#04X}")]
+    NegativeResponse { sid: u8, nrc: u8 },
 }
 
 #[derive(Debug, Serialize)]
@@ -69,6 +169,79 @@ pub struct J2534VersionInfo {
     pub fw_version: String,
 }
 
+/// A PassThru interface advertised in the Windows registry under
+/// `PassThruSupport.04.04` (or its `WOW6432Node` counterpart), with the
+/// protocol support flags read alongside the DLL path so a UI can filter
+/// devices before connecting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct J2534DeviceInfo {
+    pub name: String,
+    pub vendor: String,
+    pub dll_path: String,
+    pub can: bool,
+    pub iso15765: bool,
+    pub iso14230: bool,
+}
+
+/// Enumerate every installed J2534 PassThru device from the registry.
+///
+/// Walks `HKEY_LOCAL_MACHINE\SOFTWARE\PassThruSupport.04.04` and the
+/// `WOW6432Node` variant (32-bit DLLs registered on a 64-bit OS), reading
+/// `Name`, `Vendor`, `FunctionLibrary` and the per-protocol support flags
+/// from each vendor subkey.
+#[cfg(target_os = "windows")]
+pub fn list_installed_devices() -> Result<Vec<J2534DeviceInfo>, J2534Error> {
+    use winreg::enums::*;
+    use winreg::RegKey;
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let mut devices = Vec::new();
+
+    let paths = [
+        "SOFTWARE\\PassThruSupport.04.04",
+        "SOFTWARE\\WOW6432Node\\PassThruSupport.04.04",
+    ];
+
+    for path in paths {
+        let passthru = match hklm.open_subkey(path) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+
+        for name in passthru.enum_keys().flatten() {
+            let device_key = match passthru.open_subkey(&name) {
+                Ok(key) => key,
+                Err(_) => continue,
+            };
+
+            let dll_path: String = device_key.get_value("FunctionLibrary").unwrap_or_default();
+            if dll_path.is_empty() {
+                continue;
+            }
+
+            let read_flag = |value: &str| -> bool { device_key.get_value::<u32, _>(value).unwrap_or(0) != 0 };
+
+            devices.push(J2534DeviceInfo {
+                name: device_key.get_value("Name").unwrap_or(name),
+                vendor: device_key.get_value("Vendor").unwrap_or_default(),
+                dll_path,
+                can: read_flag("CAN"),
+                iso15765: read_flag("ISO15765"),
+                iso14230: read_flag("ISO14230"),
+            });
+        }
+    }
+
+    Ok(devices)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn list_installed_devices() -> Result<Vec<J2534DeviceInfo>, J2534Error> {
+    Err(J2534Error::LoadError(
+        "J2534 device enumeration is only supported on Windows".to_string(),
+    ))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassThruMsg {
     pub protocol_id: u32,
@@ -158,14 +331,27 @@ impl From<&PassThruMsg> for CPassThruMsg {
     }
 }
 
+/// The PassThru API generation a DLL implements. 04.02-era interfaces don't
+/// export `PassThruOpen`/`PassThruClose` and instead connect directly with a
+/// fixed device id of 0, matching the behavior diagnostic apps use when they
+/// check `libraryAPIversion() != v0202` before calling `PassThruClose`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ApiGeneration {
+    V0404,
+    V0202,
+}
+
 pub struct J2534Driver {
     lib: Arc<Library>,
+    api_generation: ApiGeneration,
     device_id: u32,
     can_channel_id: Option<u32>,
     iso_channel_id: Option<u32>,
     can_filter_ids: Vec<u32>,
     iso_filter_ids: Vec<u32>,
     iso_fc_filter_id: Option<u32>,
+    // (channel_id, msg_id) pairs for periodic messages started via `start_periodic`.
+    periodic_msg_ids: Vec<(u32, u32)>,
 }
 
 // Note: J2534Driver is wrapped in Arc<Mutex<>> in AppState for proper thread safety
@@ -173,29 +359,68 @@ pub struct J2534Driver {
 impl J2534Driver {
     pub unsafe fn new(dll_path: &str) -> Result<Self, J2534Error> {
         let lib = Library::new(dll_path).map_err(|e| J2534Error::LoadError(e.to_string()))?;
+        let api_generation = if lib
+            .get::<unsafe extern "stdcall" fn(name: *const i8, device_id: *mut u32) -> i32>(b"PassThruOpen")
+            .is_ok()
+        {
+            ApiGeneration::V0404
+        } else {
+            ApiGeneration::V0202
+        };
         Ok(J2534Driver {
             lib: Arc::new(lib),
+            api_generation,
             device_id: 0,
             can_channel_id: None,
             iso_channel_id: None,
             can_filter_ids: Vec::new(),
             iso_filter_ids: Vec::new(),
             iso_fc_filter_id: None,
+            periodic_msg_ids: Vec::new(),
+        })
+    }
+
+    /// The PassThru API generation detected for this device at load time, so
+    /// higher layers can adapt (e.g. skip features only 04.04 DLLs expose).
+    pub fn api_generation(&self) -> ApiGeneration {
+        self.api_generation
+    }
+
+    /// Load the driver for a device discovered via [`list_installed_devices`],
+    /// so a UI can offer a picker instead of requiring a hard-coded DLL path.
+    pub unsafe fn from_device_info(info: &J2534DeviceInfo) -> Result<Self, J2534Error> {
+        Self::new(&info.dll_path)
+    }
+
+    /// Turn a raw PassThru return code into a [`J2534Error`], decorating it
+    /// with the vendor's own description from `PassThruGetLastError` when
+    /// the DLL exposes one. Returns `Ok(())` for `PASSTHRU_ERR_SUCCESS`.
+    fn check(&self, res: i32) -> Result<(), J2534Error> {
+        if res == PASSTHRU_ERR_SUCCESS {
+            return Ok(());
+        }
+        Err(J2534Error::PassThruError {
+            status: PassThruStatus::from_code(res),
+            detail: self.get_last_error().ok(),
         })
     }
 
     pub fn open(&mut self) -> Result<(), J2534Error> {
+        if self.api_generation == ApiGeneration::V0202 {
+            // 04.02 DLLs don't export PassThruOpen; they connect directly with a fixed device id.
+            self.device_id = 0;
+            return Ok(());
+        }
+
         unsafe {
-            let func: Symbol<unsafe extern "stdcall" fn(name: *const i8, device_id: *mut u32) -> i32> = 
+            let func: Symbol<unsafe extern "stdcall" fn(name: *const i8, device_id: *mut u32) -> i32> =
                 self.lib.get(b"PassThruOpen").map_err(|e| J2534Error::SymbolError(e.to_string()))?;
-            
+
             let mut device_id = 0;
             // Pass null for name to open any connected device (or default)
             let res = func(std::ptr::null(), &mut device_id);
 
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             self.device_id = device_id;
             Ok(())
         }
@@ -220,9 +445,7 @@ impl J2534Driver {
 
             let mut channel_id = 0;
             let res = func(self.device_id, protocol_id, flags, baud, &mut channel_id);
-             if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             Ok(channel_id)
         }
     }
@@ -240,9 +463,7 @@ impl J2534Driver {
             let mut filter_id = 0;
             let res = func(channel_id, PASS_FILTER, &c_mask, &c_pattern, c_flow as *const CPassThruMsg, &mut filter_id);
 
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             Ok(filter_id)
         }
     }
@@ -287,9 +508,7 @@ impl J2534Driver {
             let mut filter_id = 0;
             let res = func(channel_id, FLOW_CONTROL_FILTER, &c_mask, &c_pattern, &c_flow, &mut filter_id);
 
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             Ok(filter_id)
         }
     }
@@ -300,9 +519,7 @@ impl J2534Driver {
                 self.lib.get(b"PassThruStopMsgFilter").map_err(|e| J2534Error::SymbolError(e.to_string()))?;
 
             let res = func(channel_id, filter_id);
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             Ok(())
         }
     }
@@ -321,12 +538,10 @@ impl J2534Driver {
             ];
 
             let res = func(channel_id, msgs.as_mut_ptr(), &mut num_msgs, timeout_ms);
-            if res != PASSTHRU_ERR_SUCCESS {
-                if res == ERR_BUFFER_EMPTY || res == ERR_TIMEOUT {
-                    return Ok(vec![]);
-                }
-                return Err(J2534Error::PassThruError(res));
+            if res == ERR_BUFFER_EMPTY || res == ERR_TIMEOUT {
+                return Ok(vec![]);
             }
+            self.check(res)?;
 
             let mut result = Vec::new();
             for i in 0..num_msgs as usize {
@@ -346,9 +561,7 @@ impl J2534Driver {
 
             let res = func(channel_id, c_msgs.as_mut_ptr(), &mut num_msgs, timeout_ms);
 
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
 
             Ok(())
         }
@@ -362,29 +575,90 @@ impl J2534Driver {
             if let Some(channel_id) = self.iso_channel_id.take() {
                 let _ = self.disconnect_channel(channel_id);
             }
-            if let Ok(func) = self.lib.get::<unsafe extern "stdcall" fn(device_id: u32) -> i32>(b"PassThruClose") {
-                let res = func(self.device_id);
-                if res != PASSTHRU_ERR_SUCCESS {
-                    return Err(J2534Error::PassThruError(res));
+            // 04.02 DLLs don't export PassThruClose; tearing down channels above is enough.
+            if self.api_generation == ApiGeneration::V0404 {
+                if let Ok(func) = self.lib.get::<unsafe extern "stdcall" fn(device_id: u32) -> i32>(b"PassThruClose") {
+                    let res = func(self.device_id);
+                    self.check(res)?;
                 }
             }
             Ok(())
         }
     }
 
-    pub fn disconnect_channel(&self, channel_id: u32) -> Result<(), J2534Error> {
+    pub fn disconnect_channel(&mut self, channel_id: u32) -> Result<(), J2534Error> {
+        let stale_msg_ids: Vec<u32> = self
+            .periodic_msg_ids
+            .iter()
+            .filter(|(c, _)| *c == channel_id)
+            .map(|(_, msg_id)| *msg_id)
+            .collect();
+        for msg_id in stale_msg_ids {
+            let _ = self.stop_periodic(channel_id, msg_id);
+        }
+
         unsafe {
             let func: Symbol<unsafe extern "stdcall" fn(channel_id: u32) -> i32> =
                 self.lib.get(b"PassThruDisconnect").map_err(|e| J2534Error::SymbolError(e.to_string()))?;
 
             let res = func(channel_id);
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             Ok(())
         }
     }
 
+    /// Register a periodic message (e.g. a UDS TesterPresent `3E 00` frame)
+    /// so the interface hardware retransmits it at `interval_ms` without the
+    /// caller having to spin its own timer and call `write_msgs` repeatedly.
+    pub fn start_periodic(
+        &mut self,
+        protocol: ProtocolKind,
+        msg: &PassThruMsg,
+        interval_ms: u32,
+    ) -> Result<u32, J2534Error> {
+        let channel_id = self.channel_id(protocol)?;
+        unsafe {
+            let func: Symbol<
+                unsafe extern "stdcall" fn(channel_id: u32, msg: *const CPassThruMsg, msg_id: *mut u32, time_interval: u32) -> i32,
+            > = self
+                .lib
+                .get(b"PassThruStartPeriodicMsg")
+                .map_err(|e| J2534Error::SymbolError(e.to_string()))?;
+
+            let c_msg = CPassThruMsg::from(msg);
+            let mut msg_id = 0;
+            let res = func(channel_id, &c_msg, &mut msg_id, interval_ms);
+            self.check(res)?;
+
+            self.periodic_msg_ids.push((channel_id, msg_id));
+            Ok(msg_id)
+        }
+    }
+
+    /// Cancel a periodic message started with [`start_periodic`]. `channel_id`
+    /// must match the channel it was started on — `msg_id` alone is a small
+    /// DLL-local counter and isn't guaranteed unique across channels.
+    pub fn stop_periodic(&mut self, channel_id: u32, msg_id: u32) -> Result<(), J2534Error> {
+        if !self.periodic_msg_ids.contains(&(channel_id, msg_id)) {
+            return Err(J2534Error::InvalidConfig(format!(
+                "Unknown periodic message id {msg_id} on channel {channel_id}"
+            )));
+        }
+
+        unsafe {
+            let func: Symbol<unsafe extern "stdcall" fn(channel_id: u32, msg_id: u32) -> i32> = self
+                .lib
+                .get(b"PassThruStopPeriodicMsg")
+                .map_err(|e| J2534Error::SymbolError(e.to_string()))?;
+
+            let res = func(channel_id, msg_id);
+            self.check(res)?;
+        }
+
+        self.periodic_msg_ids.retain(|&(c, id)| (c, id) != (channel_id, msg_id));
+        Ok(())
+    }
+
     pub fn channel_id(&self, protocol: ProtocolKind) -> Result<u32, J2534Error> {
         match protocol {
             ProtocolKind::Can => self.can_channel_id.ok_or_else(|| J2534Error::ChannelNotOpen("CAN".to_string())),
@@ -465,14 +739,10 @@ impl J2534Driver {
                 self.lib.get(b"PassThruIoctl").map_err(|e| J2534Error::SymbolError(e.to_string()))?;
 
             let res = func(channel_id, IOCTL_CLEAR_RX_BUFFER, std::ptr::null_mut(), std::ptr::null_mut());
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
 
             let res = func(channel_id, IOCTL_CLEAR_TX_BUFFER, std::ptr::null_mut(), std::ptr::null_mut());
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
 
             Ok(())
         }
@@ -498,9 +768,7 @@ impl J2534Driver {
                 (&mut list as *mut SConfigList) as *mut c_void,
                 std::ptr::null_mut(),
             );
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             Ok(())
         }
     }
@@ -525,9 +793,7 @@ impl J2534Driver {
                 (&mut list as *mut SConfigList) as *mut c_void,
                 std::ptr::null_mut(),
             );
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
             Ok(params.to_vec())
         }
     }
@@ -567,9 +833,7 @@ impl J2534Driver {
                 fw_buf.as_mut_ptr(),
             );
 
-            if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
-            }
+            self.check(res)?;
 
             Ok(J2534VersionInfo {
                 api_version: cstr_to_string(&api_buf),
@@ -587,7 +851,11 @@ impl J2534Driver {
             let mut buf = vec![0i8; 256];
             let res = func(buf.as_mut_ptr());
             if res != PASSTHRU_ERR_SUCCESS {
-                return Err(J2534Error::PassThruError(res));
+                // Avoid recursing back into PassThruGetLastError for its own failure.
+                return Err(J2534Error::PassThruError {
+                    status: PassThruStatus::from_code(res),
+                    detail: None,
+                });
             }
 
             Ok(cstr_to_string(&buf))
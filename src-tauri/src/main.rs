@@ -1,8 +1,14 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+// `std::simd` is nightly-only; only pull the feature in when the `simd`
+// Cargo feature is enabled so default (stable) builds are unaffected.
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
 mod j2534;
 mod gmlan; // Phase 4 addition
+mod isotp;
+mod uds;
+mod bench;
 
 use j2534::{IsoTpConfig, J2534Driver, J2534VersionInfo, PassThruMsg, ProtocolKind, SConfig};
 use std::sync::{Arc, Mutex};
@@ -155,50 +161,15 @@ fn stop_heartbeat_inner(state: &AppState) {
 
 #[tauri::command]
 fn list_j2534_devices() -> Result<Vec<J2534Device>, String> {
-    #[cfg(target_os = "windows")]
-    {
-        use winreg::enums::*;
-        use winreg::RegKey;
-
-        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
-        let mut devices = Vec::new();
-
-        let paths = [
-            "SOFTWARE\\PassThruSupport.04.04",
-            "SOFTWARE\\WOW6432Node\\PassThruSupport.04.04",
-        ];
-
-        for path in paths {
-            if let Ok(passthru) = hklm.open_subkey(path) {
-                for name in passthru.enum_keys() {
-                    let name = match name {
-                        Ok(n) => n,
-                        Err(_) => continue,
-                    };
-                    if let Ok(device_key) = passthru.open_subkey(&name) {
-                        let vendor: String = device_key.get_value("Vendor").unwrap_or_default();
-                        let name_str: String = device_key.get_value("Name").unwrap_or(name.clone());
-                        let dll_path: String = device_key.get_value("FunctionLibrary").unwrap_or_default();
-
-                        if !dll_path.is_empty() {
-                            devices.push(J2534Device {
-                                name: name_str,
-                                vendor,
-                                dll_path,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(devices)
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("J2534 is only supported on Windows".to_string())
-    }
+    let devices = j2534::list_installed_devices().map_err(|e| e.to_string())?;
+    Ok(devices
+        .into_iter()
+        .map(|d| J2534Device {
+            name: d.name,
+            vendor: d.vendor,
+            dll_path: d.dll_path,
+        })
+        .collect())
 }
 
 #[tauri::command]
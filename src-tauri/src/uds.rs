@@ -0,0 +1,83 @@
+// UDS (ISO 14229) request/response helper sitting on top of raw PassThruMsg
+// frames on the ISO15765 channel. Callers used to hand-build frame bytes and
+// manually correlate responses, including the responsePending handshake, for
+// every request — this turns that into a couple of typed calls, which is
+// what the GM A-key SecurityAccess (0x27) seed/key exchange needs.
+
+use crate::j2534::{J2534Driver, J2534Error, PassThruMsg, PassThruStatus, ProtocolKind};
+use std::time::{Duration, Instant};
+
+const NEGATIVE_RESPONSE_SID: u8 = 0x7F;
+const NRC_REQUEST_CORRECTLY_RECEIVED_RESPONSE_PENDING: u8 = 0x78;
+
+fn matching_payload(msg: &PassThruMsg, expected_id: u32) -> Option<Vec<u8>> {
+    if msg.data.len() < 4 {
+        return None;
+    }
+    let id = u32::from_be_bytes([msg.data[0], msg.data[1], msg.data[2], msg.data[3]]);
+    if id != expected_id {
+        return None;
+    }
+    Some(msg.data[4..].to_vec())
+}
+
+impl J2534Driver {
+    /// Send a UDS request (`service` + `data`) on the ISO15765 channel and
+    /// wait for the matching positive response (SID `service | 0x40`),
+    /// transparently looping past `0x7F <sid> 0x78` (responsePending)
+    /// negative responses until a final positive response or a different
+    /// NRC arrives.
+    pub fn uds_request(
+        &self,
+        tx_id: u32,
+        rx_id: u32,
+        service: u8,
+        data: &[u8],
+        timeout_ms: u32,
+    ) -> Result<Vec<u8>, J2534Error> {
+        let channel_id = self.channel_id(ProtocolKind::Iso15765)?;
+
+        let mut payload = vec![service];
+        payload.extend_from_slice(data);
+        let msg = PassThruMsg::new_iso15765(tx_id, &payload, 0);
+        self.write_msgs(channel_id, vec![msg], timeout_ms)?;
+
+        let expected_sid = service | 0x40;
+        let mut deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+
+        loop {
+            let remaining_ms = deadline.saturating_duration_since(Instant::now()).as_millis() as u32;
+            if remaining_ms == 0 {
+                return Err(J2534Error::PassThruError {
+                    status: PassThruStatus::Timeout,
+                    detail: None,
+                });
+            }
+
+            for msg in self.read_msgs(channel_id, 1, remaining_ms)? {
+                let Some(response) = matching_payload(&msg, rx_id) else {
+                    continue;
+                };
+                if response.is_empty() {
+                    continue;
+                }
+
+                if response[0] == NEGATIVE_RESPONSE_SID {
+                    if response.len() < 3 || response[1] != service {
+                        continue;
+                    }
+                    let nrc = response[2];
+                    if nrc == NRC_REQUEST_CORRECTLY_RECEIVED_RESPONSE_PENDING {
+                        deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+                        continue;
+                    }
+                    return Err(J2534Error::NegativeResponse { sid: service, nrc });
+                }
+
+                if response[0] == expected_sid {
+                    return Ok(response[1..].to_vec());
+                }
+            }
+        }
+    }
+}